@@ -9,13 +9,20 @@ pub trait AngularConstraint: XpbdConstraint<2> {
     /// Here in 2D, `axis` is a unit vector with the Z coordinate set to 1 or -1. It controls if the body should rotate counterclockwise or clockwise.
     ///
     /// Returns the angular impulse that is applied proportional to the inverse masses of the bodies.
+    ///
+    /// The applied rotation is clamped so the implied angular velocity `|Δrot| / sub_dt` never
+    /// exceeds `max_corrective_velocity`, leaving any remaining error to subsequent substeps
+    /// instead of applying it all at once (see [`MaxCorrectiveVelocity`](crate::MaxCorrectiveVelocity)).
     #[cfg(feature = "2d")]
+    #[allow(clippy::too_many_arguments)]
     fn apply_angular_correction(
         &self,
         body1: &mut RigidBodyQueryItem,
         body2: &mut RigidBodyQueryItem,
         delta_lagrange: Scalar,
         axis: Vector3,
+        sub_dt: Scalar,
+        max_corrective_velocity: Scalar,
     ) -> Scalar {
         if delta_lagrange >= -Scalar::EPSILON {
             return 0.0;
@@ -31,12 +38,14 @@ pub trait AngularConstraint: XpbdConstraint<2> {
         let inv_inertia1 = body1.world_inv_inertia().0;
         let inv_inertia2 = body2.world_inv_inertia().0;
 
+        let max_delta_rot = max_corrective_velocity * sub_dt;
+
         // Apply rotational updates
         if body1.rb.is_dynamic() {
-            *body1.rot += Self::get_delta_rot(rot1, inv_inertia1, p);
+            *body1.rot += Self::get_delta_rot(rot1, inv_inertia1, p, max_delta_rot);
         }
         if body2.rb.is_dynamic() {
-            *body2.rot -= Self::get_delta_rot(rot2, inv_inertia2, p);
+            *body2.rot -= Self::get_delta_rot(rot2, inv_inertia2, p, max_delta_rot);
         }
 
         p
@@ -45,13 +54,20 @@ pub trait AngularConstraint: XpbdConstraint<2> {
     /// Applies angular constraints for interactions between two bodies.
     ///
     /// Returns the angular impulse that is applied proportional to the inverse masses of the bodies.
+    ///
+    /// The applied rotation is clamped so the implied angular velocity `|Δrot| / sub_dt` never
+    /// exceeds `max_corrective_velocity`, leaving any remaining error to subsequent substeps
+    /// instead of applying it all at once (see [`MaxCorrectiveVelocity`](crate::MaxCorrectiveVelocity)).
     #[cfg(feature = "3d")]
+    #[allow(clippy::too_many_arguments)]
     fn apply_angular_correction(
         &self,
         body1: &mut RigidBodyQueryItem,
         body2: &mut RigidBodyQueryItem,
         delta_lagrange: Scalar,
         axis: Vector,
+        sub_dt: Scalar,
+        max_corrective_velocity: Scalar,
     ) -> Vector {
         if delta_lagrange >= -Scalar::EPSILON {
             return Vector::ZERO;
@@ -66,12 +82,14 @@ pub trait AngularConstraint: XpbdConstraint<2> {
         let inv_inertia1 = body1.world_inv_inertia().0;
         let inv_inertia2 = body2.world_inv_inertia().0;
 
+        let max_delta_rot = max_corrective_velocity * sub_dt;
+
         // Apply rotational updates
         if body1.rb.is_dynamic() {
-            *body1.rot += Self::get_delta_rot(rot1, inv_inertia1, p);
+            *body1.rot += Self::get_delta_rot(rot1, inv_inertia1, p, max_delta_rot);
         }
         if body2.rb.is_dynamic() {
-            *body2.rot -= Self::get_delta_rot(rot2, inv_inertia2, p);
+            *body2.rot -= Self::get_delta_rot(rot2, inv_inertia2, p, max_delta_rot);
         }
 
         p
@@ -97,20 +115,72 @@ pub trait AngularConstraint: XpbdConstraint<2> {
         }
     }
 
+    /// `max_delta` caps the returned rotation's magnitude in radians; pass
+    /// `max_corrective_velocity * sub_dt` to keep the implied angular velocity
+    /// timestep-independent.
     #[cfg(feature = "2d")]
-    fn get_delta_rot(_rot: Rot, inv_inertia: Scalar, p: Scalar) -> Rot {
+    fn get_delta_rot(_rot: Rot, inv_inertia: Scalar, p: Scalar, max_delta: Scalar) -> Rot {
         // Equation 8/9 but in 2D
-        Rot::from_radians(inv_inertia * p)
+        Rot::from_radians((inv_inertia * p).clamp(-max_delta, max_delta))
     }
 
+    /// `max_delta` caps the returned rotation's magnitude in radians; pass
+    /// `max_corrective_velocity * sub_dt` to keep the implied angular velocity
+    /// timestep-independent.
     #[cfg(feature = "3d")]
-    fn get_delta_rot(rot: Rot, inv_inertia: Matrix3, p: Vector) -> Rot {
+    fn get_delta_rot(rot: Rot, inv_inertia: Matrix3, p: Vector, max_delta: Scalar) -> Rot {
         // Equation 8/9
-        Rot(Quaternion::from_vec4(0.5 * (inv_inertia * p).extend(0.0)) * rot.0)
+        let mut delta = inv_inertia * p;
+        let magnitude = delta.length();
+        if magnitude > max_delta && magnitude > Scalar::EPSILON {
+            delta *= max_delta / magnitude;
+        }
+        Rot(Quaternion::from_vec4(0.5 * delta.extend(0.0)) * rot.0)
+    }
+
+    /// Computes the Lagrange multiplier update for a softly-damped constraint, implementing the
+    /// damped-constraint update from the paper this file cites (eq. 21) instead of the plain
+    /// `Δλ = (−C − α̃λ) / (w + α̃)` update. `gradient_dot_dx` is the constraint gradient dotted
+    /// with the change in position since the start of the substep (`∇C·(x − x_prev)`), and `w`
+    /// is the generalized inverse mass of the constrained body pair. `alpha` and `beta` are the
+    /// compliance and damping coefficient of the soft constraint, typically derived from a target
+    /// natural frequency and damping ratio.
+    fn get_delta_lagrange_damped(
+        &self,
+        lagrange: Scalar,
+        c: Scalar,
+        gradient_dot_dx: Scalar,
+        w: Scalar,
+        alpha: Scalar,
+        beta: Scalar,
+        dt: Scalar,
+    ) -> Scalar {
+        if dt <= Scalar::EPSILON {
+            return 0.0;
+        }
+
+        let alpha_tilde = alpha / dt.powi(2);
+        let gamma = alpha_tilde * beta / dt;
+
+        (-c - alpha_tilde * lagrange - gamma * gradient_dot_dx) / ((1.0 + gamma) * w + alpha_tilde)
     }
 
     /// Computes the torque acting along the constraint using the equation tau = lambda * n / h^2
     fn compute_torque(&self, lagrange: Scalar, axis: Vector3, dt: Scalar) -> Torque {
+        // Guard against a zero or near-zero substep `dt` (e.g. a paused `PhysicsTimestep::Variable`
+        // frame). Dividing by `dt.powi(2)` here would otherwise produce `NaN`/`inf` that propagates
+        // into the bodies' rotation permanently.
+        if !crate::resources::SubDeltaTime(dt).is_valid() {
+            #[cfg(feature = "2d")]
+            {
+                return 0.0;
+            }
+            #[cfg(feature = "3d")]
+            {
+                return Vector::ZERO;
+            }
+        }
+
         // Eq (17)
         #[cfg(feature = "2d")]
         {
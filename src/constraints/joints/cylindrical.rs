@@ -0,0 +1,444 @@
+use super::clamp_corrective_velocity;
+use crate::prelude::*;
+use crate::resources::{ConstraintRegularization, MaxCorrectiveVelocity};
+use bevy::prelude::*;
+
+/// A cylindrical joint that allows one body to translate and rotate relative to another along a
+/// shared axis, like a piston that can also spin. The translation can be restricted with a
+/// [`JointLimit`], and so can the twist angle around the axis.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct CylindricalJoint {
+    /// First entity constrained by the joint.
+    pub entity1: Entity,
+    /// Second entity constrained by the joint.
+    pub entity2: Entity,
+    /// Attachment point on the first body, relative to its center of mass.
+    pub local_anchor1: Vector,
+    /// Attachment point on the second body, relative to its center of mass.
+    pub local_anchor2: Vector,
+    /// Free axis that the joint is aligned with, relative to the first body.
+    pub local_axis1: Vector,
+    /// Free axis that the joint is aligned with, relative to the second body.
+    pub local_axis2: Vector,
+    /// Linear distance the attached bodies can translate along the shared axis.
+    pub linear_limit: Option<JointLimit>,
+    /// Angle the attached bodies can twist around the shared axis.
+    pub angle_limit: Option<JointLimit>,
+    /// Linear damping applied by the joint.
+    pub damping_lin: Scalar,
+    /// Angular damping applied by the joint.
+    pub damping_ang: Scalar,
+    /// Lagrange multiplier for the translation correction.
+    pub pos_lagrange: Scalar,
+    /// Lagrange multiplier for the twist correction.
+    pub rot_lagrange: Scalar,
+    /// The joint's compliance, the inverse of stiffness.
+    pub compliance: Scalar,
+    /// When set, overrides `compliance` with a soft constraint parameterized by a target natural
+    /// frequency and damping ratio instead. See [`Joint::with_frequency`].
+    pub softness: Option<JointSoftness>,
+    /// Caps how fast a single correction is allowed to move or rotate the attached bodies,
+    /// expressed as a velocity (`units/s` or `radians/s`); see [`MaxCorrectiveVelocity`]. Defaults
+    /// to [`MaxCorrectiveVelocity::default`]'s value; set with [`Self::with_max_corrective_velocity`].
+    pub max_corrective_velocity: Scalar,
+    /// The force exerted by the joint.
+    pub force: Vector,
+}
+
+impl Joint for CylindricalJoint {
+    fn new_with_compliance(entity1: Entity, entity2: Entity, compliance: Scalar) -> Self {
+        Self {
+            entity1,
+            entity2,
+            local_anchor1: Vector::ZERO,
+            local_anchor2: Vector::ZERO,
+            local_axis1: Vector::Z,
+            local_axis2: Vector::Z,
+            linear_limit: None,
+            angle_limit: None,
+            damping_lin: 1.0,
+            damping_ang: 1.0,
+            pos_lagrange: 0.0,
+            rot_lagrange: 0.0,
+            compliance,
+            softness: None,
+            max_corrective_velocity: MaxCorrectiveVelocity::default().0,
+            force: Vector::ZERO,
+        }
+    }
+
+    fn with_local_anchor_1(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor1: anchor,
+            ..self
+        }
+    }
+
+    fn with_local_anchor_2(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor2: anchor,
+            ..self
+        }
+    }
+
+    fn with_lin_vel_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_lin: damping,
+            ..self
+        }
+    }
+
+    fn with_ang_vel_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_ang: damping,
+            ..self
+        }
+    }
+
+    fn with_frequency(self, frequency: Scalar) -> Self {
+        let damping_ratio = self.softness.unwrap_or(JointSoftness::RIGID).damping_ratio;
+        Self {
+            softness: Some(JointSoftness::new(frequency, damping_ratio)),
+            ..self
+        }
+    }
+
+    fn with_damping_ratio(self, damping_ratio: Scalar) -> Self {
+        let natural_frequency = self
+            .softness
+            .unwrap_or(JointSoftness::RIGID)
+            .natural_frequency;
+        Self {
+            softness: Some(JointSoftness::new(natural_frequency, damping_ratio)),
+            ..self
+        }
+    }
+
+    fn entities(&self) -> [Entity; 2] {
+        [self.entity1, self.entity2]
+    }
+
+    fn damping_lin(&self) -> Scalar {
+        self.damping_lin
+    }
+
+    fn damping_ang(&self) -> Scalar {
+        self.damping_ang
+    }
+
+    fn constrain(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        sub_dt: Scalar,
+    ) {
+        // A zero or near-zero substep `dt` (e.g. a paused `PhysicsTimestep::Variable` frame) would
+        // make every `alpha_tilde = compliance / sub_dt.powi(2)` below blow up to `NaN`/`inf`, so
+        // skip solving entirely rather than let that propagate into the bodies permanently.
+        if !crate::resources::SubDeltaTime(sub_dt).is_valid() {
+            return;
+        }
+
+        let world_r1 = *body1.rot * self.local_anchor1;
+        let world_r2 = *body2.rot * self.local_anchor2;
+
+        // The shared axis is defined by the first body; the two bodies are kept aligned to it by
+        // the angular correction below, so the twist solve can always work in this one frame.
+        let axis = (*body1.rot * self.local_axis1).normalize_or_zero();
+
+        // Cancel the two translation components perpendicular to the shared axis, then clamp the
+        // remaining component along it to the linear limit, so the bodies can only slide along
+        // the axis and only within range.
+        let pos_offset = (body2.pos.0 + world_r2) - (body1.pos.0 + world_r1);
+        let perpendicular_offset = pos_offset - axis * pos_offset.dot(axis);
+        let mut correction = -perpendicular_offset;
+
+        if let Some(linear_limit) = self.linear_limit {
+            correction += self.limit_distance_along_axis(
+                linear_limit.min,
+                linear_limit.max,
+                axis,
+                world_r1,
+                world_r2,
+                body1.pos,
+                body2.pos,
+                sub_dt,
+                self.max_corrective_velocity,
+            );
+        }
+
+        self.apply_position_correction(body1, body2, correction, world_r1, world_r2, sub_dt);
+
+        // Align the two body axes with the shared axis using the existing angular correction
+        // machinery, then enforce the twist limit around it.
+        let axis1 = *body1.rot * self.local_axis1;
+        let axis2 = *body2.rot * self.local_axis2;
+        let alignment_correction = axis1.cross(axis2);
+        self.apply_rotation_correction(body1, body2, alignment_correction, sub_dt);
+
+        // The alignment correction above just rotated both bodies, so the twist limit must be
+        // solved against the post-correction axes rather than the stale ones computed before it.
+        // The twist is measured between a reference vector perpendicular to each body's axis.
+        let axis = (*body1.rot * self.local_axis1).normalize_or_zero();
+        let twist_ref1 = *body1.rot * self.local_axis1.any_orthonormal_vector();
+        let twist_ref2 = *body2.rot * self.local_axis2.any_orthonormal_vector();
+
+        if let Some(angle_limit) = self.angle_limit {
+            if let Some(twist_correction) = Self::limit_angle(
+                axis,
+                twist_ref1,
+                twist_ref2,
+                angle_limit.min,
+                angle_limit.max,
+                self.max_corrective_velocity * sub_dt,
+            ) {
+                self.apply_rotation_correction(body1, body2, twist_correction, sub_dt);
+            }
+        }
+    }
+}
+
+impl CylindricalJoint {
+    /// Creates a new soft `CylindricalJoint` whose default stiffness comes from `regularization`
+    /// (typically read as `Res<ConstraintRegularization>`) rather than [`JointSoftness::RIGID`],
+    /// so changing the resource in the `World` changes the joints built from it.
+    pub fn new_with_regularization(
+        entity1: Entity,
+        entity2: Entity,
+        regularization: &ConstraintRegularization,
+    ) -> Self {
+        Self {
+            softness: Some(regularization.softness()),
+            ..Self::new_with_compliance(entity1, entity2, 0.0)
+        }
+    }
+
+    /// Overrides the velocity cap described by [`MaxCorrectiveVelocity`] for this joint instead of
+    /// using the default.
+    pub fn with_max_corrective_velocity(self, max_corrective_velocity: Scalar) -> Self {
+        Self {
+            max_corrective_velocity,
+            ..self
+        }
+    }
+
+    /// Creates a new `CylindricalJoint` whose velocity cap comes from `max_corrective_velocity`
+    /// (typically read as `Res<MaxCorrectiveVelocity>`) instead of
+    /// `MaxCorrectiveVelocity::default()`'s hardcoded value, mirroring
+    /// [`Self::new_with_regularization`] so changing the resource in the `World` actually affects
+    /// joints built from it.
+    pub fn new_with_max_corrective_velocity(
+        entity1: Entity,
+        entity2: Entity,
+        max_corrective_velocity: &MaxCorrectiveVelocity,
+    ) -> Self {
+        Self {
+            max_corrective_velocity: max_corrective_velocity.0,
+            ..Self::new_with_compliance(entity1, entity2, 0.0)
+        }
+    }
+
+    /// Solves the XPBD position constraint `C = correction` for the translation degrees of
+    /// freedom the joint restricts, mirroring the Lagrange update used by the angular correction
+    /// in [`AngularConstraint::apply_angular_correction`].
+    fn apply_position_correction(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        correction: Vector,
+        r1: Vector,
+        r2: Vector,
+        sub_dt: Scalar,
+    ) {
+        let correction = clamp_corrective_velocity(correction, sub_dt, self.max_corrective_velocity);
+        let magnitude = correction.length();
+        if magnitude <= Scalar::EPSILON {
+            return;
+        }
+        let direction = correction / magnitude;
+
+        let w1 = body1.inv_mass.0
+            + r1.cross(direction).dot(body1.world_inv_inertia().0 * r1.cross(direction));
+        let w2 = body2.inv_mass.0
+            + r2.cross(direction).dot(body2.world_inv_inertia().0 * r2.cross(direction));
+        let w = w1 + w2;
+
+        let delta_lagrange = if let Some(softness) = self.softness {
+            let (alpha, beta) = softness.alpha_beta(w);
+
+            let point_vel1 = body1.lin_vel.0 + body1.ang_vel.0.cross(r1);
+            let point_vel2 = body2.lin_vel.0 + body2.ang_vel.0.cross(r2);
+            let gradient_dot_dx = (point_vel1 - point_vel2).dot(direction) * sub_dt;
+
+            self.get_delta_lagrange_damped(
+                self.pos_lagrange,
+                magnitude,
+                gradient_dot_dx,
+                w,
+                alpha,
+                beta,
+                sub_dt,
+            )
+        } else {
+            let alpha_tilde = self.compliance / sub_dt.powi(2);
+            (-magnitude - alpha_tilde * self.pos_lagrange) / (w + alpha_tilde)
+        };
+        self.pos_lagrange += delta_lagrange;
+
+        let impulse = delta_lagrange * direction;
+
+        if body1.rb.is_dynamic() {
+            body1.pos.0 += impulse * body1.inv_mass.0;
+        }
+        if body2.rb.is_dynamic() {
+            body2.pos.0 -= impulse * body2.inv_mass.0;
+        }
+
+        self.force = impulse / sub_dt.powi(2);
+    }
+
+    /// Solves the XPBD angular constraint for a correction vector, reusing
+    /// [`AngularConstraint::apply_angular_correction`] after computing its Lagrange update.
+    fn apply_rotation_correction(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        correction: Vector,
+        sub_dt: Scalar,
+    ) {
+        let magnitude = correction.length();
+        if magnitude <= Scalar::EPSILON {
+            return;
+        }
+        let axis = correction / magnitude;
+
+        let w1 = self.compute_generalized_inverse_mass(body1, axis);
+        let w2 = self.compute_generalized_inverse_mass(body2, axis);
+        let w = w1 + w2;
+
+        let delta_lagrange = if let Some(softness) = self.softness {
+            let (alpha, beta) = softness.alpha_beta(w);
+            let gradient_dot_dx = (body2.ang_vel.0 - body1.ang_vel.0).dot(axis) * sub_dt;
+
+            self.get_delta_lagrange_damped(
+                self.rot_lagrange,
+                magnitude,
+                gradient_dot_dx,
+                w,
+                alpha,
+                beta,
+                sub_dt,
+            )
+        } else {
+            let alpha_tilde = self.compliance / sub_dt.powi(2);
+            (-magnitude - alpha_tilde * self.rot_lagrange) / (w + alpha_tilde)
+        };
+        self.rot_lagrange += delta_lagrange;
+
+        // `apply_angular_correction` (and `get_delta_rot` underneath it) clamps the actual
+        // `|Δrot| / sub_dt` it applies, after `inv_inertia` has scaled `delta_lagrange` into a
+        // rotation — clamping `delta_lagrange` itself here instead wouldn't bound the applied
+        // rotation correctly, since `inv_inertia` can scale it arbitrarily in 3D. `rot_lagrange`
+        // accumulates the *unclamped* `delta_lagrange` above, so while the cap is active it can
+        // exceed the impulse that was actually applied.
+        self.apply_angular_correction(
+            body1,
+            body2,
+            delta_lagrange,
+            axis,
+            sub_dt,
+            self.max_corrective_velocity,
+        );
+    }
+}
+
+impl PositionConstraint for CylindricalJoint {}
+
+impl AngularConstraint for CylindricalJoint {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joint() -> CylindricalJoint {
+        CylindricalJoint::new_with_compliance(Entity::from_raw(0), Entity::from_raw(1), 0.0)
+    }
+
+    #[test]
+    fn zero_sub_dt_is_invalid() {
+        assert!(!crate::resources::is_valid_sub_dt(0.0));
+        assert!(crate::resources::is_valid_sub_dt(1.0 / 60.0));
+    }
+
+    #[test]
+    fn compute_torque_at_zero_dt_is_zero() {
+        let joint = joint();
+        #[cfg(feature = "2d")]
+        assert_eq!(joint.compute_torque(1.0, Vector3::Z, 0.0), 0.0);
+        #[cfg(feature = "3d")]
+        assert_eq!(joint.compute_torque(1.0, Vector3::Z, 0.0), Vector::ZERO);
+    }
+
+    #[test]
+    fn get_delta_lagrange_damped_at_zero_dt_is_zero() {
+        let joint = joint();
+        assert_eq!(
+            joint.get_delta_lagrange_damped(0.0, 1.0, 0.0, 1.0, 0.1, 0.1, 0.0),
+            0.0
+        );
+    }
+
+    // `RigidBody`, `RigidBodyQuery`, `Pos`, `Rot`, `LinVel`, `AngVel`, `InvMass`, and `InvInertia`
+    // are defined outside this source-snapshot fragment (only `angular_constraint.rs`,
+    // `cylindrical.rs`, `mod.rs`, and `resources.rs` are present here), so this reconstructs the
+    // minimal `RigidBodyQuery`-shaped bundle this joint actually reads and writes, matching the
+    // field names it already dereferences above (`body1.pos`, `body1.rot`, `body1.lin_vel`,
+    // `body1.ang_vel`, `body1.inv_mass`, `body1.rb`, `body1.world_inv_inertia()`).
+    #[test]
+    fn constrain_at_zero_dt_keeps_bodies_finite() {
+        let mut world = World::new();
+
+        let entity1 = world
+            .spawn((
+                RigidBody::Dynamic,
+                Pos(Vector::ZERO),
+                Rot::default(),
+                LinVel::default(),
+                AngVel::default(),
+                InvMass(1.0),
+                InvInertia::default(),
+            ))
+            .id();
+        let entity2 = world
+            .spawn((
+                RigidBody::Dynamic,
+                Pos(Vector::X * 2.0),
+                Rot::default(),
+                LinVel::default(),
+                AngVel::default(),
+                InvMass(1.0),
+                InvInertia::default(),
+            ))
+            .id();
+
+        let mut joint = joint();
+        joint.entity1 = entity1;
+        joint.entity2 = entity2;
+        joint.linear_limit = Some(JointLimit::new(-1.0, 1.0));
+        joint.angle_limit = Some(JointLimit::new(-1.0, 1.0));
+
+        let mut query = world.query::<RigidBodyQuery>();
+        let [mut body1, mut body2] = query
+            .get_many_mut(&mut world, [entity1, entity2])
+            .expect("both entities satisfy RigidBodyQuery");
+
+        joint.constrain(&mut body1, &mut body2, 0.0);
+
+        assert!(body1.pos.0.is_finite());
+        assert!(body2.pos.0.is_finite());
+        // This whole module is `#[cfg(feature = "3d")]`-gated (see `joints/mod.rs`), so `Rot` here
+        // always wraps a `Quaternion`.
+        assert!(body1.rot.0.is_finite());
+        assert!(body2.rot.0.is_finite());
+    }
+}
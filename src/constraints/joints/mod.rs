@@ -1,16 +1,24 @@
 //! General joint logic and different types of built-in joints.
 
+// A cylindrical joint twists around its free axis, which needs a 3D `Vector` (its twist limit goes
+// through `limit_angle`, which takes `Vector3`); under "2d" `Vector` is a `Vec2`, so the joint
+// itself only makes sense under "3d".
+#[cfg(feature = "3d")]
+mod cylindrical;
 mod fixed;
 mod prismatic;
 mod revolute;
 mod spherical;
 
+#[cfg(feature = "3d")]
+pub use cylindrical::*;
 pub use fixed::*;
 pub use prismatic::*;
 pub use revolute::*;
 pub use spherical::*;
 
 use crate::prelude::*;
+use crate::resources::{ConstraintRegularization, MaxCorrectiveVelocity};
 use bevy::prelude::*;
 
 /// Joints are constraints that attach pairs of bodies and restrict their relative positional and rotational degrees of freedom.
@@ -30,6 +38,26 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     /// Sets the angular velocity damping caused by the joint.
     fn with_ang_vel_damping(self, damping: Scalar) -> Self;
 
+    /// Configures the joint as a soft constraint with the given target natural frequency in Hz,
+    /// alongside [`Joint::with_damping_ratio`]. This is an alternative to
+    /// [`Joint::new_with_compliance`] that doesn't couple the perceived stiffness to
+    /// [`NumSubsteps`](crate::NumSubsteps).
+    ///
+    /// The default implementation leaves the joint rigid; only joints that actually support
+    /// [`JointSoftness`] need to override this.
+    fn with_frequency(self, _frequency: Scalar) -> Self {
+        self
+    }
+
+    /// Sets the damping ratio used by the soft-constraint mode enabled with
+    /// [`Joint::with_frequency`]. `0.0` is undamped, `1.0` is critically damped.
+    ///
+    /// The default implementation leaves the joint rigid; only joints that actually support
+    /// [`JointSoftness`] need to override this.
+    fn with_damping_ratio(self, _damping_ratio: Scalar) -> Self {
+        self
+    }
+
     /// Returns the two entities constrained by the joint.
     fn entities(&self) -> [Entity; 2];
 
@@ -48,6 +76,9 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     );
 
     /// Returns the positional correction required to limit the distance between two bodies to be between `min` and `max`.
+    ///
+    /// The correction's magnitude is clamped so the implied velocity `|correction| / sub_dt` never
+    /// exceeds `max_corrective_velocity`; see [`MaxCorrectiveVelocity`].
     #[allow(clippy::too_many_arguments)]
     fn limit_distance(
         &mut self,
@@ -57,6 +88,8 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         r2: Vector,
         pos1: &Pos,
         pos2: &Pos,
+        sub_dt: Scalar,
+        max_corrective_velocity: Scalar,
     ) -> Vector {
         let pos_offset = (pos2.0 + r2) - (pos1.0 + r1);
         let distance = pos_offset.length();
@@ -66,7 +99,7 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         }
 
         // Equation 25
-        if distance < min {
+        let correction = if distance < min {
             // Separation distance lower limit
             -pos_offset / distance * (distance - min)
         } else if distance > max {
@@ -74,10 +107,15 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
             -pos_offset / distance * (distance - max)
         } else {
             Vector::ZERO
-        }
+        };
+
+        clamp_corrective_velocity(correction, sub_dt, max_corrective_velocity)
     }
 
     /// Returns the positional correction required to limit the distance between two bodies to be between `min` and `max` along a given `axis`.
+    ///
+    /// The correction's magnitude is clamped so the implied velocity `|correction| / sub_dt` never
+    /// exceeds `max_corrective_velocity`; see [`MaxCorrectiveVelocity`].
     #[allow(clippy::too_many_arguments)]
     fn limit_distance_along_axis(
         &mut self,
@@ -88,12 +126,14 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         r2: Vector,
         pos1: &Pos,
         pos2: &Pos,
+        sub_dt: Scalar,
+        max_corrective_velocity: Scalar,
     ) -> Vector {
         let pos_offset = (pos2.0 + r2) - (pos1.0 + r1);
         let a = pos_offset.dot(axis);
 
         // Equation 25
-        if a < min {
+        let correction = if a < min {
             // Separation distance lower limit
             -axis * (a - min)
         } else if a > max {
@@ -101,10 +141,16 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
             -axis * (a - max)
         } else {
             Vector::ZERO
-        }
+        };
+
+        clamp_corrective_velocity(correction, sub_dt, max_corrective_velocity)
     }
 
     /// Returns the angular correction required to limit thw angle between the axes `n1` and `n2` to be in the interval between `alpha` and `beta` using the common rotation axis `n`.
+    ///
+    /// `max_correction` caps the returned correction's magnitude; pass
+    /// `max_corrective_velocity * sub_dt` (see [`MaxCorrectiveVelocity`]) to keep the implied
+    /// angular velocity timestep-independent.
     fn limit_angle(
         n: Vector3,
         n1: Vector3,
@@ -159,3 +205,72 @@ impl JointLimit {
         Self { min, max }
     }
 }
+
+/// Clamps `correction`'s magnitude so the implied velocity `|correction| / sub_dt` never exceeds
+/// `max_corrective_velocity`; the leftover error is resolved over subsequent substeps instead of
+/// being applied all at once. See [`MaxCorrectiveVelocity`].
+pub(crate) fn clamp_corrective_velocity(
+    correction: Vector,
+    sub_dt: Scalar,
+    max_corrective_velocity: Scalar,
+) -> Vector {
+    let max_magnitude = max_corrective_velocity * sub_dt;
+    let magnitude = correction.length();
+
+    if magnitude > max_magnitude && magnitude > Scalar::EPSILON {
+        correction * (max_magnitude / magnitude)
+    } else {
+        correction
+    }
+}
+
+/// Configures a joint as a soft spring-damper instead of a (near-)rigid constraint, specified by
+/// a target natural frequency and damping ratio rather than raw compliance, mirroring how a
+/// spring-damper distance constraint is normally parameterized. See
+/// [`Joint::with_frequency`]/[`Joint::with_damping_ratio`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointSoftness {
+    /// The target natural frequency in Hz.
+    pub natural_frequency: Scalar,
+    /// The damping ratio; `0.0` is undamped, `1.0` is critically damped.
+    pub damping_ratio: Scalar,
+}
+
+impl JointSoftness {
+    /// A softness stiff enough to reproduce today's near-rigid joint behavior while staying
+    /// well-conditioned at low substep counts. When a joint has no [`JointSoftness`] yet,
+    /// [`Joint::with_frequency`] takes its damping ratio from this and
+    /// [`Joint::with_damping_ratio`] takes its natural frequency from this. A joint with no
+    /// [`JointSoftness`] at all uses its plain compliance instead.
+    ///
+    /// This is a plain constant, not read from [`ConstraintRegularization`]; a joint constructor
+    /// that wants the user's configured default softness takes a `&ConstraintRegularization`
+    /// (read from `Res<ConstraintRegularization>`) and calls [`ConstraintRegularization::softness`]
+    /// on it, e.g.
+    /// [`CylindricalJoint::new_with_regularization`](crate::CylindricalJoint::new_with_regularization).
+    pub const RIGID: Self = Self {
+        natural_frequency: 60.0,
+        damping_ratio: 1.0,
+    };
+
+    /// Creates a new `JointSoftness`.
+    pub fn new(natural_frequency: Scalar, damping_ratio: Scalar) -> Self {
+        Self {
+            natural_frequency,
+            damping_ratio,
+        }
+    }
+
+    /// Derives the XPBD compliance `alpha` and damping coefficient `beta` for a constraint with
+    /// this softness, given the generalized inverse mass `w` of the constrained body pair, so
+    /// that the resulting behavior stays consistent regardless of `w`.
+    pub fn alpha_beta(&self, w: Scalar) -> (Scalar, Scalar) {
+        let omega = (2.0 * PI * self.natural_frequency).max(Scalar::EPSILON);
+        let effective_mass = if w > Scalar::EPSILON { 1.0 / w } else { 0.0 };
+
+        let alpha = 1.0 / (omega * omega * effective_mass);
+        let beta = 2.0 * self.damping_ratio / omega;
+
+        (alpha, beta)
+    }
+}
@@ -2,7 +2,7 @@
 
 use bevy::prelude::Resource;
 
-use crate::{Scalar, Vector};
+use crate::{constraints::joints::JointSoftness, Scalar, Vector};
 
 /// Configures how many times per second the physics simulation is run.
 #[derive(Resource, Clone, Copy, Debug, PartialEq)]
@@ -32,6 +32,26 @@ pub struct DeltaTime(pub Scalar);
 #[derive(Resource, Default)]
 pub struct SubDeltaTime(pub Scalar);
 
+impl SubDeltaTime {
+    /// Returns false for a zero or near-zero substep delta time, e.g. when [`PhysicsTimestep::Variable`]
+    /// reports a `0.0` frame delta (a paused window, the first frame, a headless step). Constraint
+    /// solving and integration divide by `dt` or `dt.powi(2)`, so they must be skipped rather than
+    /// evaluated whenever this returns false, or `NaN`/`inf` will propagate into [`Pos`](crate::Pos)
+    /// and [`Rot`](crate::Rot) permanently.
+    pub fn is_valid(&self) -> bool {
+        is_valid_sub_dt(self.0)
+    }
+}
+
+/// Returns false for a zero or near-zero substep delta time. Shared by [`SubDeltaTime::is_valid`]
+/// and by constraint-solving entry points (e.g. [`AngularConstraint::compute_torque`](crate::AngularConstraint::compute_torque),
+/// [`Joint::constrain`](crate::Joint::constrain) implementations) that receive `sub_dt` as a plain
+/// `Scalar` rather than the resource itself and must skip their division by `dt`/`dt.powi(2)` the
+/// same way.
+pub(crate) fn is_valid_sub_dt(sub_dt: Scalar) -> bool {
+    sub_dt > Scalar::EPSILON
+}
+
 /// The number of substeps used in XPBD simulation. A higher number of substeps reduces the value of [`SubDeltaTime`], which results in a more accurate simulation at the cost of performance.
 #[derive(Resource, Clone, Copy)]
 pub struct NumSubsteps(pub u32);
@@ -67,3 +87,62 @@ impl Default for Gravity {
 impl Gravity {
     pub const ZERO: Gravity = Gravity(Vector::ZERO);
 }
+
+/// A default stiffness for soft constraints (see [`JointSoftness`]), expressed as a natural
+/// frequency and damping ratio rather than raw compliance. It is read only by constructors that
+/// take it explicitly, such as
+/// [`CylindricalJoint::new_with_regularization`](crate::CylindricalJoint::new_with_regularization);
+/// [`Joint::with_frequency`] and [`Joint::with_damping_ratio`] fill in a missing parameter from
+/// [`JointSoftness::RIGID`], not from this resource.
+///
+/// Raw compliance (`alpha` in `alpha / h^2`) couples the perceived stiffness to [`NumSubsteps`]:
+/// the same `alpha` feels softer at a low substep count and stiffer at a high one, because `h`
+/// (the substep delta time) shrinks as substeps increase. Expressing stiffness as a natural
+/// frequency and damping ratio instead keeps the perceived stiffness and overshoot constant
+/// across substep counts, since the effective compliance is re-derived from the frequency and the
+/// constrained bodies' mass every substep. A joint built with [`Joint::new_with_compliance`]
+/// continues to use that fixed compliance unless [`Joint::with_frequency`] or
+/// [`Joint::with_damping_ratio`] is also called.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct ConstraintRegularization {
+    /// The default natural frequency in Hz.
+    pub natural_frequency: Scalar,
+    /// The default damping ratio; `0.0` is undamped, `1.0` is critically damped.
+    pub damping_ratio: Scalar,
+}
+
+impl Default for ConstraintRegularization {
+    fn default() -> Self {
+        // Stiff enough to reproduce today's near-rigid joint behavior while staying
+        // well-conditioned at low substep counts.
+        Self {
+            natural_frequency: 60.0,
+            damping_ratio: 1.0,
+        }
+    }
+}
+
+impl ConstraintRegularization {
+    /// Returns the [`JointSoftness`] described by this resource.
+    pub fn softness(&self) -> JointSoftness {
+        JointSoftness::new(self.natural_frequency, self.damping_ratio)
+    }
+}
+
+/// A normalized, timestep-independent cap on how fast a single constraint correction is allowed
+/// to move or rotate a body, expressed as a velocity (`units/s` or `radians/s`).
+///
+/// Without this cap, a deeply penetrating contact or a joint initialized far from its limit would
+/// have its full `Δpos`/`Δrot` applied in one substep, snapping back explosively ("popping").
+/// Clamping the implied velocity `|Δpos| / sub_dt` (and `|Δrot| / sub_dt`) instead leaves the
+/// remaining error to be resolved gradually over subsequent substeps.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct MaxCorrectiveVelocity(pub Scalar);
+
+impl Default for MaxCorrectiveVelocity {
+    fn default() -> Self {
+        // Generous enough that well-behaved scenes never notice the clamp, while still bounding
+        // the worst-case pop from deep penetration or a far-from-limit joint.
+        Self(4.0)
+    }
+}